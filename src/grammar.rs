@@ -0,0 +1,58 @@
+use std::path::Path;
+
+use libloading::{Library, Symbol};
+use tree_sitter::Language;
+
+/// File extensions swept for each built-in language name, keyed by the value
+/// passed to `--language`. Extend this table (and the `.so`/`.dll` beside it
+/// in `--grammar-dir`) to add a new language without touching the traversal
+/// or parsing code.
+const LANGUAGE_EXTENSIONS: &[(&str, &[&str])] = &[
+    ("c", &["c", "h"]),
+    ("cpp", &["cpp", "cc", "cxx", "hpp", "hh"]),
+    ("rust", &["rs"]),
+    ("go", &["go"]),
+];
+
+/// Returns the file extensions associated with `language`, or an empty slice
+/// if the language isn't in the built-in table.
+pub fn extensions_for(language: &str) -> &'static [&'static str] {
+    LANGUAGE_EXTENSIONS
+        .iter()
+        .find(|(name, _)| *name == language)
+        .map(|(_, exts)| *exts)
+        .unwrap_or(&[])
+}
+
+/// Loads the tree-sitter `Language` for `language`.
+///
+/// With no `grammar_dir`, only the grammar statically linked into this crate
+/// (`c`) is available. Otherwise the grammar is loaded at runtime by
+/// `dlopen`-ing `<grammar_dir>/<language><DLL_SUFFIX>` and calling its
+/// `tree_sitter_<language>` symbol, the same convention editors use to load
+/// tree-sitter grammars without recompiling.
+pub fn load_language(language: &str, grammar_dir: Option<&Path>) -> Result<Language, String> {
+    let Some(dir) = grammar_dir else {
+        return match language {
+            "c" => Ok(tree_sitter_c::language()),
+            other => Err(format!(
+                "unknown language '{other}': pass --grammar-dir to load it from a shared library"
+            )),
+        };
+    };
+
+    let lib_path = dir.join(format!("{language}{}", std::env::consts::DLL_SUFFIX));
+    // Leaked deliberately: the `Language` handle we return borrows the
+    // library's symbol table for the remainder of the process.
+    let library = unsafe { Library::new(&lib_path) }
+        .map_err(|e| format!("failed to load grammar '{}': {e}", lib_path.display()))?;
+    let library: &'static Library = Box::leak(Box::new(library));
+
+    let symbol_name = format!("tree_sitter_{language}");
+    unsafe {
+        let constructor: Symbol<unsafe extern "C" fn() -> Language> = library
+            .get(symbol_name.as_bytes())
+            .map_err(|e| format!("grammar '{language}' missing symbol '{symbol_name}': {e}"))?;
+        Ok(constructor())
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Resolves `#include` directives into the set of headers reachable from a
+/// translation unit, so declarations can be checked against what a call
+/// site can actually see instead of a flat per-project count.
+pub struct IncludeGraph {
+    include_dirs: Vec<PathBuf>,
+}
+
+struct Include {
+    path: String,
+    /// `true` for `#include "..."`, `false` for `#include <...>`.
+    local: bool,
+}
+
+impl IncludeGraph {
+    pub fn new(include_dirs: Vec<PathBuf>) -> Self {
+        Self { include_dirs }
+    }
+
+    /// Returns every header transitively reachable from `tu` via
+    /// `#include`, plus `tu` itself. Paths that can't be canonicalized
+    /// (missing files, broken includes) are skipped rather than failing
+    /// the whole resolution.
+    pub fn reachable_headers(&self, tu: &Path) -> HashSet<PathBuf> {
+        let mut reachable = HashSet::new();
+        let mut stack = vec![tu.to_path_buf()];
+
+        while let Some(file) = stack.pop() {
+            let Ok(canonical) = file.canonicalize() else {
+                continue;
+            };
+            if !reachable.insert(canonical) {
+                continue; // already visited, avoid include cycles
+            }
+            let Ok(content) = fs::read_to_string(&file) else {
+                continue;
+            };
+            for include in parse_includes(&content) {
+                if let Some(resolved) = self.resolve(&include, file.parent()) {
+                    stack.push(resolved);
+                }
+            }
+        }
+
+        reachable
+    }
+
+    /// Resolves one `#include`: the local include dir (the file's own
+    /// directory) is searched first for `"..."` includes, then the
+    /// configured `--include-dir` list is searched for both forms, mirroring
+    /// the compiler's local-then-system search order.
+    fn resolve(&self, include: &Include, local_dir: Option<&Path>) -> Option<PathBuf> {
+        if include.local {
+            if let Some(dir) = local_dir {
+                let candidate = dir.join(&include.path);
+                if candidate.is_file() {
+                    return Some(candidate);
+                }
+            }
+        }
+        self.include_dirs
+            .iter()
+            .map(|dir| dir.join(&include.path))
+            .find(|candidate| candidate.is_file())
+    }
+}
+
+/// Scans `content` line by line for `#include "..."` / `#include <...>`
+/// directives. Deliberately simple text scanning rather than a grammar
+/// rule, since `IncludeGraph` needs to walk headers that may not have been
+/// swept (and so were never parsed) themselves.
+fn parse_includes(content: &str) -> Vec<Include> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("#include")?.trim();
+            if let Some(rest) = rest.strip_prefix('"') {
+                let end = rest.find('"')?;
+                Some(Include {
+                    path: rest[..end].to_string(),
+                    local: true,
+                })
+            } else if let Some(rest) = rest.strip_prefix('<') {
+                let end = rest.find('>')?;
+                Some(Include {
+                    path: rest[..end].to_string(),
+                    local: false,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a fresh, empty temp directory for a test fixture, named after
+    /// the calling test and the process id so parallel test runs don't
+    /// collide.
+    fn temp_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "c_function_sweeper-include_graph-test-{name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn transitive_includes_are_followed() {
+        let dir = temp_test_dir("transitive");
+        fs::write(dir.join("b.h"), "int b(void);\n").unwrap();
+        fs::write(dir.join("a.h"), "#include \"b.h\"\nint a(void);\n").unwrap();
+        let tu = dir.join("tu.c");
+        fs::write(&tu, "#include \"a.h\"\nint main(void) { return 0; }\n").unwrap();
+
+        let reachable = IncludeGraph::new(Vec::new()).reachable_headers(&tu);
+
+        assert!(reachable.contains(&tu.canonicalize().unwrap()));
+        assert!(reachable.contains(&dir.join("a.h").canonicalize().unwrap()));
+        assert!(reachable.contains(&dir.join("b.h").canonicalize().unwrap()));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn include_cycles_terminate() {
+        let dir = temp_test_dir("cycle");
+        fs::write(dir.join("a.h"), "#include \"b.h\"\nint a(void);\n").unwrap();
+        fs::write(dir.join("b.h"), "#include \"a.h\"\nint b(void);\n").unwrap();
+        let tu = dir.join("tu.c");
+        fs::write(&tu, "#include \"a.h\"\n").unwrap();
+
+        // Must return rather than looping forever on the a.h <-> b.h cycle.
+        let reachable = IncludeGraph::new(Vec::new()).reachable_headers(&tu);
+
+        assert_eq!(reachable.len(), 3);
+        assert!(reachable.contains(&dir.join("a.h").canonicalize().unwrap()));
+        assert!(reachable.contains(&dir.join("b.h").canonicalize().unwrap()));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn local_quote_include_prefers_tu_directory_over_include_dirs() {
+        let dir = temp_test_dir("precedence-local");
+        let system_dir = dir.join("system");
+        fs::create_dir_all(&system_dir).unwrap();
+
+        fs::write(dir.join("config.h"), "int local_config(void);\n").unwrap();
+        fs::write(system_dir.join("config.h"), "int system_config(void);\n").unwrap();
+        let tu = dir.join("tu.c");
+        fs::write(&tu, "#include \"config.h\"\n").unwrap();
+
+        let reachable = IncludeGraph::new(vec![system_dir.clone()]).reachable_headers(&tu);
+
+        assert!(reachable.contains(&dir.join("config.h").canonicalize().unwrap()));
+        assert!(!reachable.contains(&system_dir.join("config.h").canonicalize().unwrap()));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn angle_include_is_resolved_from_include_dirs() {
+        let dir = temp_test_dir("precedence-system");
+        let system_dir = dir.join("system");
+        fs::create_dir_all(&system_dir).unwrap();
+
+        fs::write(system_dir.join("lib.h"), "int lib_fn(void);\n").unwrap();
+        let tu = dir.join("tu.c");
+        fs::write(&tu, "#include <lib.h>\n").unwrap();
+
+        let reachable = IncludeGraph::new(vec![system_dir.clone()]).reachable_headers(&tu);
+
+        assert!(reachable.contains(&system_dir.join("lib.h").canonicalize().unwrap()));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
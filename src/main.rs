@@ -1,16 +1,26 @@
+mod grammar;
+mod include_graph;
+mod paths;
+mod queries;
+mod report;
+
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::{fs, usize};
+use std::fs;
+
+use include_graph::IncludeGraph;
+use report::{Finding, FindingKind};
 
 use clap::Parser;
-use tree_sitter::{Node, Parser as TsParser};
+use rayon::prelude::*;
+use tree_sitter::{Node, Parser as TsParser, Query, QueryCursor};
 use walkdir::WalkDir;
 
 #[derive(Parser, Debug)]
 #[command(
     version,
-    about = "Simple C function sweeper",
-    long_about = "Search for unused or undeclared C functions"
+    about = "Simple function sweeper",
+    long_about = "Search for unused or undeclared functions"
 )]
 struct Args {
     /// Path or file to sweep
@@ -20,6 +30,36 @@ struct Args {
     /// Search folders recursively
     #[arg(short, long)]
     recursive: bool,
+
+    /// Language to sweep (selects the grammar and swept file extensions)
+    #[arg(short = 'l', long, default_value = "c")]
+    language: String,
+
+    /// Directory containing tree-sitter grammar shared libraries
+    /// (`<language>.so`/`.dll`), loaded at runtime via the `tree_sitter_<language>` symbol
+    #[arg(short = 'g', long)]
+    grammar_dir: Option<PathBuf>,
+
+    /// Directory containing per-language `.scm` capture queries
+    /// (`<language>.scm`), tagging declaration/call sites with `@declaration`/`@call`
+    #[arg(short = 'q', long)]
+    queries_dir: Option<PathBuf>,
+
+    /// Directory searched to resolve `#include` directives (C only), in
+    /// addition to each translation unit's own directory; its files are also
+    /// swept, for every language (repeatable)
+    #[arg(short = 'I', long = "include-dir")]
+    include_dirs: Vec<PathBuf>,
+
+    /// Report output format
+    #[arg(short = 'f', long, value_enum, default_value_t = report::Format::Text)]
+    format: report::Format,
+
+    /// Project root that reported paths are rendered relative to
+    /// (defaults to the `--path` directory); falls back to a
+    /// current-working-directory-relative path for findings outside it
+    #[arg(long)]
+    root: Option<PathBuf>,
 }
 
 #[derive(Default)]
@@ -35,32 +75,201 @@ fn main() {
     let recursive = args.recursive;
 
     // Setup Tree-sitter parser
-    let mut parser = TsParser::new();
-    parser
-        .set_language(&tree_sitter_c::language())
-        .expect("Error loading C grammar");
+    let language = match grammar::load_language(&args.language, args.grammar_dir.as_deref()) {
+        Ok(language) => language,
+        Err(e) => {
+            eprintln!("Error loading '{}' grammar: {e}", args.language);
+            std::process::exit(1);
+        }
+    };
+    let extensions = grammar::extensions_for(&args.language);
+
+    let query = match queries::load_query(&language, &args.language, args.queries_dir.as_deref())
+    {
+        Ok(query) => query,
+        Err(e) => {
+            eprintln!("Error loading '{}' query: {e}", args.language);
+            std::process::exit(1);
+        }
+    };
+
+    // Collect the files to sweep up front so parsing can be parallelized
+    // over a plain list of paths.
+    let files = collect_swept_files(path, recursive, &args.include_dirs, extensions, &args.language);
+
+    // Parse each file on its own `rayon` worker (with its own `TsParser`,
+    // since a parser can't be shared across threads), then fold the
+    // per-file stats into the global map.
+    let function_stats: HashMap<String, FunctionStats> = files
+        .par_iter()
+        .map(|file_path| {
+            let mut parser = TsParser::new();
+            parser
+                .set_language(language)
+                .expect("Error loading grammar");
+            let mut local_stats = HashMap::new();
+            parse_file(file_path, &mut parser, &query, &mut local_stats);
+            local_stats
+        })
+        .reduce(HashMap::new, merge_function_stats);
+
+    // Sort the function stats by name for deterministic output
+    let mut function_stats: Vec<(String, FunctionStats)> = function_stats.into_iter().collect();
+    function_stats.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    // Reported paths are rendered relative to this root rather than
+    // whatever absolute/`WalkDir`-derived form was used internally.
+    let root = args.root.clone().unwrap_or_else(|| {
+        if path.is_dir() {
+            path.to_path_buf()
+        } else {
+            path.parent().unwrap_or(Path::new(".")).to_path_buf()
+        }
+    });
+
+    let include_graph = IncludeGraph::new(args.include_dirs.clone());
+    let mut reachable_headers_cache = HashMap::new();
+    let mut findings = Vec::new();
+
+    for (function_name, stats) in function_stats {
+        if function_name == "main" {
+            continue;
+        };
+        findings.extend(findings_for_function(
+            &function_name,
+            &stats,
+            &args.language,
+            &include_graph,
+            &mut reachable_headers_cache,
+            &root,
+        ));
+    }
+
+    report::print_report(&findings, args.format);
+}
+
+/// Builds the unused/undeclared findings for one function, with paths
+/// rendered relative to `root`.
+fn findings_for_function(
+    function_name: &str,
+    stats: &FunctionStats,
+    language: &str,
+    include_graph: &IncludeGraph,
+    reachable_headers_cache: &mut HashMap<PathBuf, std::collections::HashSet<PathBuf>>,
+    root: &Path,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
 
-    // Track function stats
-    let mut function_stats: HashMap<String, FunctionStats> = HashMap::new();
+    if stats.calls.is_empty() {
+        findings.extend(stats.declarations.iter().map(|(file, row, col)| {
+            Finding::new(
+                FindingKind::Unused,
+                function_name,
+                &paths::display_path(file, root),
+                *row,
+                *col,
+            )
+        }));
+    }
+
+    // For C, a function is "undeclared" when some call site's reachable
+    // headers (its own TU plus everything it `#include`s) contain none
+    // of the function's declarations. Other languages don't have
+    // `#include` semantics yet, so they fall back to the old
+    // any-more-than-one-declaration heuristic.
+    let undeclared = if language == "c" {
+        stats.calls.iter().any(|(call_file, _, _)| {
+            !is_reachable_from(
+                call_file,
+                &stats.declarations,
+                include_graph,
+                reachable_headers_cache,
+            )
+        })
+    } else {
+        stats.declarations.len() < 2
+    };
+
+    if undeclared {
+        if stats.declarations.is_empty() {
+            // No declaration exists anywhere in the swept tree (the
+            // textbook "undeclared function" case - a typo'd name or a
+            // truly missing prototype). There's no declaration site to
+            // anchor the finding at, so anchor it at every call site
+            // instead of dropping the finding altogether.
+            findings.extend(stats.calls.iter().map(|(file, row, col)| {
+                Finding::new(
+                    FindingKind::Undeclared,
+                    function_name,
+                    &paths::display_path(file, root),
+                    *row,
+                    *col,
+                )
+            }));
+        } else {
+            findings.extend(stats.declarations.iter().map(|(file, row, col)| {
+                Finding::new(
+                    FindingKind::Undeclared,
+                    function_name,
+                    &paths::display_path(file, root),
+                    *row,
+                    *col,
+                )
+            }));
+        }
+    }
+
+    findings
+}
+
+/// Returns whether some declaration of the function is visible from
+/// `call_file`, i.e. lives in one of `call_file`'s reachable headers.
+/// Reachable-header sets are cached per call-site file since many calls in
+/// the same TU share the same include closure.
+fn is_reachable_from(
+    call_file: &Path,
+    declarations: &[(PathBuf, usize, usize)],
+    include_graph: &IncludeGraph,
+    cache: &mut HashMap<PathBuf, std::collections::HashSet<PathBuf>>,
+) -> bool {
+    let reachable = cache
+        .entry(call_file.to_path_buf())
+        .or_insert_with(|| include_graph.reachable_headers(call_file));
+
+    declarations.iter().any(|(decl_file, _, _)| {
+        decl_file
+            .canonicalize()
+            .map(|canonical| reachable.contains(&canonical))
+            .unwrap_or(false)
+    })
+}
+
+/// Walks `path` (recursively when `recursive` is set) and returns every file
+/// whose extension matches `extensions`. `path` itself is returned if it's
+/// already a matching file. `language` is only used to name the grammar in
+/// the error printed when a single `--path` file doesn't match.
+fn collect_source_files(
+    path: &Path,
+    recursive: bool,
+    extensions: &[&str],
+    language: &str,
+) -> Vec<PathBuf> {
+    let mut files = Vec::new();
 
     if path.is_dir() {
-        // Traverse the directory and find C source and header files
         if let Ok(entries) = fs::read_dir(path) {
             if recursive {
-                // Search recursively
                 for entry in WalkDir::new(path).into_iter().filter_map(Result::ok) {
-                    let file_path = entry.path();
-                    if is_source_or_header_file(&file_path) {
-                        parse_file(file_path, &mut parser, &mut function_stats);
+                    let file_path = entry.path().to_path_buf();
+                    if is_source_or_header_file(&file_path, extensions) {
+                        files.push(file_path);
                     }
                 }
             } else {
-                for entry in entries {
-                    if let Ok(entry) = entry {
-                        let file_path = entry.path();
-                        if is_source_or_header_file(&file_path) {
-                            parse_file(&file_path, &mut parser, &mut function_stats);
-                        }
+                for entry in entries.filter_map(Result::ok) {
+                    let file_path = entry.path();
+                    if is_source_or_header_file(&file_path, extensions) {
+                        files.push(file_path);
                     }
                 }
             }
@@ -68,11 +277,11 @@ fn main() {
             eprintln!("Could not read directory: {}", path.display());
         }
     } else if path.is_file() {
-        if is_source_or_header_file(&path) {
-            parse_file(&path, &mut parser, &mut function_stats);
+        if is_source_or_header_file(path, extensions) {
+            files.push(path.to_path_buf());
         } else {
             eprintln!(
-                "The specified file is not a C source or header file: {}",
+                "The specified file is not a '{language}' source or header file: {}",
                 path.display()
             );
         }
@@ -83,43 +292,80 @@ fn main() {
         );
     }
 
-    // Print the function stats
-    for (function_name, stats) in function_stats {
-        if function_name == "main" {
-            continue;
-        };
-        if stats.calls.len() == 0 {
-            println!("Unused Function '{}':", function_name);
-            for (file, line, col) in stats.declarations.iter() {
-                println!("-> {} {}:{}", file.display(), line, col);
-            }
-        }
-        if stats.declarations.len() < 2 {
-            println!("Undeclared Function '{}':", function_name);
-            for (file, line, col) in stats.declarations.iter() {
-                println!("-> {} {}:{}", file.display(), line, col);
-            }
+    files
+}
+
+/// Collects every file to sweep: `path` itself (recursively, when
+/// `recursive` is set), plus the contents of each `--include-dir` (always
+/// recursively, regardless of `recursive`). `--include-dir` headers are
+/// swept too, not just used for `IncludeGraph` reachability: otherwise a
+/// function declared only in a header outside `path` would never have its
+/// declaration recorded, and would be wrongly reported undeclared no matter
+/// what `IncludeGraph` says is reachable from the call site. Directories
+/// that don't exist are skipped rather than producing a "neither a file nor
+/// a directory" error, since `include_dirs` is also allowed to name directories that
+/// simply have nothing to resolve in them.
+fn collect_swept_files(
+    path: &Path,
+    recursive: bool,
+    include_dirs: &[PathBuf],
+    extensions: &[&str],
+    language: &str,
+) -> Vec<PathBuf> {
+    let mut files = collect_source_files(path, recursive, extensions, language);
+    for include_dir in include_dirs {
+        if include_dir.is_dir() {
+            files.extend(collect_source_files(
+                include_dir,
+                true,
+                extensions,
+                language,
+            ));
         }
     }
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// Folds `b`'s declarations/calls into `a`, returning `a`. Used as the
+/// reducer (with an empty map as identity) for the per-file
+/// `FunctionStats` maps produced by the parallel parse.
+fn merge_function_stats(
+    mut a: HashMap<String, FunctionStats>,
+    b: HashMap<String, FunctionStats>,
+) -> HashMap<String, FunctionStats> {
+    for (name, stats) in b {
+        let entry = a.entry(name).or_default();
+        entry.declarations.extend(stats.declarations);
+        entry.calls.extend(stats.calls);
+    }
+    a
 }
 
-fn is_source_or_header_file(path: &Path) -> bool {
-    return path
-        .extension()
+fn is_source_or_header_file(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
         .and_then(|s| s.to_str())
-        .map(|s| s == "c" || s == "h")
-        .unwrap_or(false);
+        .map(|s| extensions.contains(&s))
+        .unwrap_or(false)
 }
 
 fn parse_file(
     path: &Path,
     parser: &mut TsParser,
+    query: &Query,
     function_stats: &mut HashMap<String, FunctionStats>,
 ) {
     if let Ok(content) = fs::read_to_string(path) {
         if let Some(tree) = parser.parse(&content, None) {
             // Find and update function declarations and calls
-            find_function_stats(tree.root_node(), path, function_stats, content.as_bytes());
+            find_function_stats(
+                tree.root_node(),
+                query,
+                path,
+                function_stats,
+                content.as_bytes(),
+            );
         } else {
             eprintln!("Could not parse file: {}", path.display());
         }
@@ -129,40 +375,123 @@ fn parse_file(
 }
 
 fn find_function_stats(
-    node: Node,
+    root: Node,
+    query: &Query,
     path: &Path,
     function_stats: &mut HashMap<String, FunctionStats>,
     source: &[u8],
 ) {
-    // Traverse the syntax tree to find function declarations and call nodes
-    match node.kind() {
-        "function_declarator" => {
-            if let Some(declarator) = node.child_by_field_name("declarator") {
-                let function_name = declarator.utf8_text(source).unwrap();
-                let stats = function_stats.entry(function_name.to_string()).or_default();
-                stats.declarations.push((
-                    path.to_path_buf(),
-                    declarator.start_position().row,
-                    declarator.start_position().column,
-                ));
-            }
-        }
-        "call_expression" => {
-            if let Some(function_name_node) = node.child_by_field_name("function") {
-                let function_name = function_name_node.utf8_text(source).unwrap();
-                let stats = function_stats.entry(function_name.to_string()).or_default();
-                stats.calls.push((
-                    path.to_path_buf(),
-                    function_name_node.start_position().row,
-                    function_name_node.start_position().column,
-                ));
+    // Run the capture query and route each match to declarations/calls by
+    // which capture name it tagged the function-name node with.
+    let declaration_idx = query.capture_index_for_name("declaration");
+    let call_idx = query.capture_index_for_name("call");
+
+    let mut cursor = QueryCursor::new();
+    for m in cursor.matches(query, root, source) {
+        for capture in m.captures {
+            let Ok(function_name) = capture.node.utf8_text(source) else {
+                continue;
+            };
+            let stats = function_stats.entry(function_name.to_string()).or_default();
+            let position = capture.node.start_position();
+            let entry = (path.to_path_buf(), position.row, position.column);
+
+            if Some(capture.index) == declaration_idx {
+                stats.declarations.push(entry);
+            } else if Some(capture.index) == call_idx {
+                stats.calls.push(entry);
             }
         }
-        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undeclared_call_with_no_declarations_still_emits_a_finding() {
+        let include_graph = IncludeGraph::new(Vec::new());
+        let mut cache = HashMap::new();
+        let mut stats = FunctionStats::default();
+        stats
+            .calls
+            .push((PathBuf::from("tu.c"), 4, 2));
+
+        let findings = findings_for_function(
+            "mystery",
+            &stats,
+            "c",
+            &include_graph,
+            &mut cache,
+            Path::new("."),
+        );
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].kind, FindingKind::Undeclared);
+        assert_eq!(findings[0].function, "mystery");
+        // 1-based, converted from the call site's 0-based (row, column).
+        assert_eq!(findings[0].line, 5);
+        assert_eq!(findings[0].column, 3);
+    }
+
+    #[test]
+    fn declared_and_called_function_is_not_flagged_as_undeclared() {
+        let dir = temp_test_dir("known-fn");
+        let tu = dir.join("tu.c");
+        fs::write(&tu, "void known(void);\nvoid caller(void) { known(); }\n").unwrap();
+
+        let include_graph = IncludeGraph::new(Vec::new());
+        let mut cache = HashMap::new();
+        let mut stats = FunctionStats::default();
+        stats.declarations.push((tu.clone(), 0, 5));
+        stats.calls.push((tu.clone(), 1, 20));
+
+        let findings =
+            findings_for_function("known", &stats, "c", &include_graph, &mut cache, &dir);
+
+        assert!(findings.is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn collect_swept_files_includes_include_dir_contents() {
+        let dir = temp_test_dir("include-dir-sweep");
+        let src_dir = dir.join("src");
+        let include_dir = dir.join("include");
+        fs::create_dir_all(&src_dir).unwrap();
+        fs::create_dir_all(&include_dir).unwrap();
+
+        let tu = src_dir.join("tu.c");
+        fs::write(&tu, "#include \"known.h\"\nvoid caller(void) { known(); }\n").unwrap();
+        let header = include_dir.join("known.h");
+        fs::write(&header, "void known(void);\n").unwrap();
+
+        let files = collect_swept_files(
+            &src_dir,
+            false,
+            std::slice::from_ref(&include_dir),
+            &["c", "h"],
+            "c",
+        );
+
+        assert!(files.contains(&tu));
+        assert!(files.contains(&header));
+
+        fs::remove_dir_all(&dir).unwrap();
     }
 
-    // Recursively search the child nodes
-    for child in node.children(&mut node.walk()) {
-        find_function_stats(child, path, function_stats, source);
+    /// Creates a fresh, empty temp directory for a test fixture, named after
+    /// the calling test and the process id so parallel test runs don't
+    /// collide.
+    fn temp_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "c_function_sweeper-test-{name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
     }
 }
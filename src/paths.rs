@@ -0,0 +1,68 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Renders `path` relative to `root` when `path` lives under `root`,
+/// falling back to a path relative to the current working directory when it
+/// doesn't. Keeps reported paths stable and diffable across machines and CI
+/// runners instead of whatever absolute/`WalkDir`-derived form was used
+/// internally.
+pub fn display_path(path: &Path, root: &Path) -> PathBuf {
+    relative_to(path, root)
+        .or_else(|| {
+            let cwd = env::current_dir().ok()?;
+            relative_to(path, &cwd)
+        })
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+fn relative_to(path: &Path, base: &Path) -> Option<PathBuf> {
+    let path = path.canonicalize().ok()?;
+    let base = base.canonicalize().ok()?;
+    path.strip_prefix(&base).ok().map(Path::to_path_buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Creates a fresh, empty temp directory for a test fixture, named after
+    /// the calling test and the process id so parallel test runs don't
+    /// collide.
+    fn temp_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "c_function_sweeper-paths-test-{name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn display_path_renders_relative_to_root_when_under_it() {
+        let root = temp_test_dir("under-root");
+        let sub = root.join("src");
+        fs::create_dir_all(&sub).unwrap();
+        let file = sub.join("tu.c");
+        fs::write(&file, "").unwrap();
+
+        assert_eq!(display_path(&file, &root), Path::new("src/tu.c"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn display_path_falls_back_to_unchanged_path_when_it_cannot_be_canonicalized() {
+        // A path that doesn't exist on disk can't be canonicalized at all,
+        // so neither the root-relative nor the cwd-relative attempt can
+        // succeed; display_path must fall back to the path unchanged rather
+        // than panicking or dropping it.
+        let root = temp_test_dir("missing-path");
+        let missing = root.join("does-not-exist.c");
+
+        assert_eq!(display_path(&missing, &root), missing);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,39 @@
+use std::fs;
+use std::path::Path;
+
+use tree_sitter::{Language, Query};
+
+/// Built-in capture query for the C grammar, used when no `--queries-dir` is
+/// given. Mirrors the declarator/call-expression recognition the original
+/// hand-written traversal performed.
+const DEFAULT_C_QUERY: &str = r#"
+(function_declarator declarator: (_) @declaration)
+(call_expression function: (_) @call)
+"#;
+
+/// Loads the capture query for `language_name`: either the file
+/// `<queries_dir>/<language_name>.scm`, or, when no `queries_dir` is given,
+/// the crate's built-in C query. Queries are expected to tag the function
+/// name node of each declaration/definition with `@declaration` and of each
+/// call site with `@call`.
+pub fn load_query(
+    language: &Language,
+    language_name: &str,
+    queries_dir: Option<&Path>,
+) -> Result<Query, String> {
+    let source = match queries_dir {
+        Some(dir) => {
+            let path = dir.join(format!("{language_name}.scm"));
+            fs::read_to_string(&path)
+                .map_err(|e| format!("failed to read query file '{}': {e}", path.display()))?
+        }
+        None if language_name == "c" => DEFAULT_C_QUERY.to_string(),
+        None => {
+            return Err(format!(
+                "no built-in query for '{language_name}': pass --queries-dir with a '{language_name}.scm' file"
+            ))
+        }
+    };
+
+    Query::new(*language, &source).map_err(|e| format!("invalid query for '{language_name}': {e}"))
+}
@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+/// Output format selected by `--format`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// The original human-readable grouped listing.
+    #[default]
+    Text,
+    /// One JSON object per finding.
+    Json,
+    /// SARIF, for GitHub/GitLab code-scanning steps.
+    Sarif,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum FindingKind {
+    Unused,
+    Undeclared,
+}
+
+impl fmt::Display for FindingKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FindingKind::Unused => write!(f, "Unused"),
+            FindingKind::Undeclared => write!(f, "Undeclared"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Finding {
+    pub kind: FindingKind,
+    pub function: String,
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Finding {
+    /// Builds a finding from tree-sitter's 0-based `(row, column)`,
+    /// converting to the 1-based line/column conventional for diagnostics
+    /// and SARIF.
+    pub fn new(kind: FindingKind, function: &str, file: &Path, row: usize, column: usize) -> Self {
+        Self {
+            kind,
+            function: function.to_string(),
+            file: file.to_path_buf(),
+            line: row + 1,
+            column: column + 1,
+        }
+    }
+}
+
+/// Prints `findings` in `format`.
+pub fn print_report(findings: &[Finding], format: Format) {
+    match format {
+        Format::Text => print_text(findings),
+        Format::Json => print_json(findings),
+        Format::Sarif => print_sarif(findings),
+    }
+}
+
+fn print_text(findings: &[Finding]) {
+    print!("{}", render_text(findings));
+}
+
+fn print_json(findings: &[Finding]) {
+    match render_json(findings) {
+        Ok(json) => println!("{json}"),
+        Err(e) => eprintln!("Failed to serialize findings as JSON: {e}"),
+    }
+}
+
+fn print_sarif(findings: &[Finding]) {
+    match render_sarif(findings) {
+        Ok(json) => println!("{json}"),
+        Err(e) => eprintln!("Failed to serialize SARIF report: {e}"),
+    }
+}
+
+/// Renders the original "Kind Function 'name':" listing with a
+/// "-> file line:column" line per finding, one trailing newline per line
+/// printed.
+fn render_text(findings: &[Finding]) -> String {
+    let mut groups: BTreeMap<(FindingKind, &str), Vec<&Finding>> = BTreeMap::new();
+    for finding in findings {
+        groups
+            .entry((finding.kind, &finding.function))
+            .or_default()
+            .push(finding);
+    }
+
+    let mut out = String::new();
+    for ((kind, function), findings) in groups {
+        out.push_str(&format!("{kind} Function '{function}':\n"));
+        for finding in findings {
+            out.push_str(&format!(
+                "-> {} {}:{}\n",
+                finding.file.display(),
+                finding.line,
+                finding.column
+            ));
+        }
+    }
+    out
+}
+
+fn render_json(findings: &[Finding]) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(findings)
+}
+
+/// Builds a minimal SARIF 2.1.0 log: one `result` per finding, with
+/// `ruleId` set to the finding kind so code-scanning UIs can group by it.
+fn render_sarif(findings: &[Finding]) -> serde_json::Result<String> {
+    let results: Vec<serde_json::Value> = findings
+        .iter()
+        .map(|finding| {
+            serde_json::json!({
+                "ruleId": finding.kind.to_string().to_lowercase(),
+                "message": { "text": format!("{} function '{}'", finding.kind, finding.function) },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": finding.file.to_string_lossy() },
+                        "region": { "startLine": finding.line, "startColumn": finding.column }
+                    }
+                }]
+            })
+        })
+        .collect();
+
+    let sarif = serde_json::json!({
+        "version": "2.1.0",
+        "$schema": "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/master/Schemata/sarif-schema-2.1.0.json",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "c_function_sweeper",
+                    "informationUri": "https://github.com/Demizo/c_function_sweeper",
+                    "rules": [
+                        { "id": "unused" },
+                        { "id": "undeclared" }
+                    ]
+                }
+            },
+            "results": results
+        }]
+    });
+
+    serde_json::to_string_pretty(&sarif)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_findings() -> Vec<Finding> {
+        vec![
+            Finding::new(FindingKind::Unused, "helper", Path::new("src/a.c"), 2, 4),
+            Finding::new(FindingKind::Undeclared, "mystery", Path::new("src/b.c"), 0, 7),
+        ]
+    }
+
+    #[test]
+    fn render_text_groups_findings_by_kind_and_function() {
+        let rendered = render_text(&sample_findings());
+
+        assert_eq!(
+            rendered,
+            "Unused Function 'helper':\n\
+             -> src/a.c 3:5\n\
+             Undeclared Function 'mystery':\n\
+             -> src/b.c 1:8\n"
+        );
+    }
+
+    #[test]
+    fn render_json_emits_one_object_per_finding() {
+        let rendered = render_json(&sample_findings()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+
+        let findings = value.as_array().unwrap();
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0]["kind"], "unused");
+        assert_eq!(findings[0]["function"], "helper");
+        assert_eq!(findings[0]["line"], 3);
+        assert_eq!(findings[0]["column"], 5);
+    }
+
+    #[test]
+    fn render_sarif_emits_one_result_per_finding_with_rule_ids() {
+        let rendered = render_sarif(&sample_findings()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+
+        assert_eq!(value["version"], "2.1.0");
+        let results = value["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleId"], "unused");
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["region"]["startLine"],
+            3
+        );
+    }
+}